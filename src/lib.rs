@@ -7,14 +7,193 @@ use nix::{
 use serde::Deserialize;
 use std::{
     io::{BufRead as _, Read, Write},
-    os::unix::fs::{MetadataExt, OpenOptionsExt as _, PermissionsExt as _},
+    net::TcpStream,
+    os::{
+        fd::{AsRawFd, OwnedFd},
+        unix::{
+            fs::{MetadataExt, OpenOptionsExt as _, PermissionsExt as _},
+            net::UnixListener,
+            process::CommandExt as _,
+        },
+    },
     path::PathBuf,
     process::Stdio,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::Duration,
 };
 use tracing::{error, info, trace, warn};
 use valuable::Valuable;
 
-#[derive(Deserialize, Valuable)]
+/// How a service should be treated once its process exits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Valuable)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Run the service once; never relaunch it.
+    #[default]
+    Never,
+    /// Relaunch the service only when it exits with a non-zero status or is killed by a signal.
+    OnFailure,
+    /// Always relaunch the service, even after a clean exit.
+    Always,
+}
+
+fn default_backoff_initial() -> u64 {
+    1
+}
+
+fn default_backoff_max() -> u64 {
+    60
+}
+
+/// How long a service must stay up before its backoff is treated as reset.
+const HEALTHY_AFTER: Duration = Duration::from_secs(60);
+
+fn default_health_status_min() -> u16 {
+    200
+}
+
+fn default_health_status_max() -> u16 {
+    399
+}
+
+/// How a service's liveness is actually checked.
+#[derive(Deserialize, Valuable, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum HealthCheck {
+    /// Run a command; a zero exit status means healthy.
+    Exec {
+        exec: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// Consider the service healthy if a TCP connection can be established.
+    Tcp { addr: String },
+    /// Issue an HTTP/1.0 `GET` for the URL's path and check the response status code falls in range.
+    Http {
+        url: String,
+        #[serde(default = "default_health_status_min")]
+        status_min: u16,
+        #[serde(default = "default_health_status_max")]
+        status_max: u16,
+    },
+}
+
+#[derive(Deserialize, Valuable, Clone, PartialEq, Eq)]
+pub struct HealthProbe {
+    #[serde(flatten)]
+    pub check: HealthCheck,
+    /// Seconds between probes.
+    #[serde(default = "default_health_interval")]
+    pub interval: u64,
+    /// Seconds to wait for a single probe to complete before treating it as failed.
+    #[serde(default = "default_health_timeout")]
+    pub timeout: u64,
+    /// Consecutive failures required before the service is considered `Down`.
+    #[serde(default = "default_health_retries")]
+    pub retries: u32,
+}
+
+fn default_health_interval() -> u64 {
+    10
+}
+
+fn default_health_timeout() -> u64 {
+    5
+}
+
+fn default_health_retries() -> u32 {
+    3
+}
+
+/// Tri-state health of a supervised service, as reported by its `health` probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Valuable)]
+pub enum HealthStatus {
+    Unknown,
+    Up,
+    Down,
+}
+
+/// Shared, cross-thread view of one service's liveness, used both to escalate
+/// an essential service's health loss and to let dependants gate their startup on it.
+#[derive(Clone)]
+pub struct ServiceHandle {
+    health: Arc<Mutex<HealthStatus>>,
+    spawned: Arc<AtomicBool>,
+    has_probe: bool,
+    pid: Arc<Mutex<Option<Pid>>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl ServiceHandle {
+    fn new(has_probe: bool) -> Self {
+        Self {
+            health: Arc::new(Mutex::new(HealthStatus::Unknown)),
+            spawned: Arc::new(AtomicBool::new(false)),
+            has_probe,
+            pid: Arc::new(Mutex::new(None)),
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Current health as last reported by the probe thread (`Unknown` if no probe is configured).
+    pub fn health(&self) -> HealthStatus {
+        *self.health.lock().unwrap()
+    }
+
+    fn set_health(&self, status: HealthStatus) -> HealthStatus {
+        let mut guard = self.health.lock().unwrap();
+        std::mem::replace(&mut *guard, status)
+    }
+
+    /// Whether the service's process has been spawned at least once.
+    pub fn is_spawned(&self) -> bool {
+        self.spawned.load(Ordering::SeqCst)
+    }
+
+    fn mark_spawned(&self) {
+        self.spawned.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether this service is ready to be depended on: `Up` if it has a health probe,
+    /// otherwise simply having been spawned.
+    pub fn is_ready(&self) -> bool {
+        if self.has_probe {
+            self.health() == HealthStatus::Up
+        } else {
+            self.is_spawned()
+        }
+    }
+
+    fn set_pid(&self, pid: Option<Pid>) {
+        let mut guard = self.pid.lock().unwrap();
+        match (guard.is_some(), pid.is_some()) {
+            (false, true) => {
+                RUNNING_CHILDREN.fetch_add(1, Ordering::SeqCst);
+            }
+            (true, false) => {
+                RUNNING_CHILDREN.fetch_sub(1, Ordering::SeqCst);
+            }
+            _ => {}
+        }
+        *guard = pid;
+    }
+
+    fn stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+
+    /// Marks this service for removal and returns the pid of its current process, if any,
+    /// so the caller can signal it directly.
+    fn request_stop(&self) -> Option<Pid> {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        *self.pid.lock().unwrap()
+    }
+}
+
+#[derive(Deserialize, Valuable, Clone, PartialEq, Eq)]
 pub struct ServiceConfig {
     pub title: String,
     pub exec: String,
@@ -22,19 +201,122 @@ pub struct ServiceConfig {
     pub args: Vec<String>,
     #[serde(default)]
     pub essential: bool,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Initial backoff, in seconds, before the first restart.
+    #[serde(default = "default_backoff_initial")]
+    pub backoff_initial: u64,
+    /// Backoff ceiling, in seconds; doubles on each consecutive failure up to this cap.
+    #[serde(default = "default_backoff_max")]
+    pub backoff_max: u64,
+    /// Give up restarting (and escalate if essential) after this many consecutive restarts.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Readiness/liveness probe; if absent, readiness is just "process spawned".
+    #[serde(default)]
+    pub health: Option<HealthProbe>,
+    /// Services that should be started before this one, with no readiness wait.
+    #[serde(default)]
+    pub after: Vec<String>,
+    /// Services that must be ready (per their `health` probe, or simply spawned) before
+    /// this one is launched.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Names of top-level `sockets` entries whose listening FDs are passed to this service
+    /// via the `LISTEN_FDS`/`LISTEN_PID`/`LISTEN_FDNAMES` convention.
+    #[serde(default)]
+    pub sockets: Vec<String>,
 }
 
-#[derive(Deserialize, Valuable)]
+#[derive(Deserialize, Valuable, Clone, PartialEq, Eq)]
 pub struct Template {
     pub src: String,
     pub dest: String,
 }
 
+/// The address a pre-opened listening socket binds to.
+#[derive(Deserialize, Valuable, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SocketKind {
+    Tcp { addr: String },
+    Unix { path: String },
+}
+
+/// A listening socket whaleinit opens itself and hands off to services by name, so the
+/// socket stays bound across restarts and reloads of whichever service owns it. Note that
+/// the `[[sockets]]` section itself is NOT hot-reloadable: it's read once at startup, and a
+/// change to it on a live config reload only produces a warning, not a rebind.
+#[derive(Deserialize, Valuable, Clone, PartialEq, Eq)]
+pub struct SocketConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: SocketKind,
+}
+
+/// A POSIX signal as written in `Config`, e.g. `"SIGTERM"` or `"TERM"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigSignal(pub Signal);
+
+fn parse_signal(name: &str) -> Option<Signal> {
+    match name.to_ascii_uppercase().trim_start_matches("SIG") {
+        "TERM" => Some(Signal::SIGTERM),
+        "INT" => Some(Signal::SIGINT),
+        "KILL" => Some(Signal::SIGKILL),
+        "HUP" => Some(Signal::SIGHUP),
+        "QUIT" => Some(Signal::SIGQUIT),
+        "USR1" => Some(Signal::SIGUSR1),
+        "USR2" => Some(Signal::SIGUSR2),
+        _ => None,
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ConfigSignal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        parse_signal(&name)
+            .map(ConfigSignal)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown signal: {name}")))
+    }
+}
+
+impl Valuable for ConfigSignal {
+    fn as_value(&self) -> valuable::Value<'_> {
+        valuable::Value::String(self.0.as_str())
+    }
+
+    fn visit(&self, visit: &mut dyn valuable::Visit) {
+        visit.visit_value(self.as_value());
+    }
+}
+
+fn default_shutdown_signals() -> Vec<ConfigSignal> {
+    [Signal::SIGTERM, Signal::SIGINT, Signal::SIGKILL]
+        .into_iter()
+        .map(ConfigSignal)
+        .collect()
+}
+
+fn default_shutdown_grace() -> u64 {
+    10
+}
+
 #[derive(Deserialize, Valuable)]
 pub struct Config {
     pub services: Vec<ServiceConfig>,
     #[serde(default)]
     pub templates: Vec<Template>,
+    #[serde(default)]
+    pub sockets: Vec<SocketConfig>,
+    /// Signals sent in order during shutdown; each is followed by up to `shutdown_grace`
+    /// of waiting for children to exit before escalating to the next.
+    #[serde(default = "default_shutdown_signals")]
+    pub shutdown_signals: Vec<ConfigSignal>,
+    /// Seconds to wait for children to exit after each shutdown signal.
+    #[serde(default = "default_shutdown_grace")]
+    pub shutdown_grace: u64,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -60,40 +342,59 @@ pub enum Error {
     WriteTemplate { dest: String, error: std::io::Error },
     #[error("Failed to change template ownership: {dest}: {error}")]
     ChangeTemplateOwnership { dest: String, error: std::io::Error },
+    #[error("Dependency cycle detected among services: {}", services.join(", "))]
+    DependencyCycle { services: Vec<String> },
+    #[error("Failed to read config: {0}: {1}")]
+    ReadConfig(PathBuf, std::io::Error),
+    #[error("Failed to render config: {0}: {1}")]
+    RenderConfig(PathBuf, liquid::Error),
+    #[error("Failed to parse config: {0}: {1}")]
+    ParseConfig(PathBuf, toml::de::Error),
+    #[error("Failed to bind socket: {name}: {error}")]
+    BindSocket { name: String, error: std::io::Error },
 }
 
-fn trigger_shutdown(initial: Signal) {
-    let signal_step = [Signal::SIGTERM, Signal::SIGINT, Signal::SIGKILL];
-    let mut step = signal_step
-        .iter()
-        .position(|s| *s == initial)
-        .unwrap_or_else(|| {
-            info!(signal = initial.as_str(), "Send signal to all processes");
-            if let Err(e) = nix::sys::signal::kill(Pid::from_raw(-1), initial) {
-                error!(
-                    error = e.to_string(),
-                    signal = initial.as_str(),
-                    "failed to send signal"
-                );
-                std::thread::sleep(std::time::Duration::from_secs(3));
-                0
-            } else {
-                0
-            }
-        });
+/// How many of whaleinit's own supervised children currently have a live process.
+/// Tracked here (rather than re-derived via `waitpid`) so shutdown can poll it without
+/// racing `reap_children`'s own blocking `wait()` loop.
+static RUNNING_CHILDREN: AtomicUsize = AtomicUsize::new(0);
+
+/// The escalation sequence and per-step grace period `trigger_shutdown` uses, set once
+/// from `Config` at boot and read from signal-handler context thereafter.
+static SHUTDOWN_PLAN: OnceLock<(Vec<Signal>, Duration)> = OnceLock::new();
+
+fn configure_shutdown(signals: Vec<Signal>, grace: Duration) {
+    let _ = SHUTDOWN_PLAN.set((signals, grace));
+}
+
+/// Sends each configured shutdown signal to every process in turn, waiting up to the
+/// configured grace period after each for whaleinit's children to exit (checked via
+/// `RUNNING_CHILDREN`) before escalating to the next signal.
+fn trigger_shutdown() {
+    let (signals, grace) = SHUTDOWN_PLAN.get_or_init(|| {
+        (
+            vec![Signal::SIGTERM, Signal::SIGINT, Signal::SIGKILL],
+            Duration::from_secs(10),
+        )
+    });
 
-    while let Some(signal) = signal_step.get(step) {
-        info!(signal = signal.as_str(), "Send signal to all processes");
+    for signal in signals {
+        info!(signal = signal.as_str(), "send signal to all processes");
         if let Err(e) = nix::sys::signal::kill(Pid::from_raw(-1), *signal) {
             error!(
                 error = e.to_string(),
                 signal = signal.as_str(),
                 "failed to send signal"
             );
-            std::thread::sleep(std::time::Duration::from_secs(3));
-            step += 1;
-        } else {
-            break;
+            continue;
+        }
+
+        let deadline = std::time::Instant::now() + *grace;
+        while std::time::Instant::now() < deadline {
+            if RUNNING_CHILDREN.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(200));
         }
     }
 }
@@ -117,74 +418,381 @@ fn print_log<R: Read>(out: R, title: &str, log_type: &str) {
     }
 }
 
-fn handle(service: &ServiceConfig) -> Result<(), Error> {
+/// A listening socket whaleinit opened and keeps bound for the lifetime of the process,
+/// independent of whichever service instance it is currently handed off to.
+struct BoundSocket {
+    name: String,
+    fd: OwnedFd,
+}
+
+fn open_socket(config: &SocketConfig) -> Result<BoundSocket, Error> {
+    let fd = match &config.kind {
+        SocketKind::Tcp { addr } => {
+            let listener =
+                std::net::TcpListener::bind(addr).map_err(|e| Error::BindSocket {
+                    name: config.name.clone(),
+                    error: e,
+                })?;
+            OwnedFd::from(listener)
+        }
+        SocketKind::Unix { path } => {
+            let _ = std::fs::remove_file(path);
+            let listener = UnixListener::bind(path).map_err(|e| Error::BindSocket {
+                name: config.name.clone(),
+                error: e,
+            })?;
+            OwnedFd::from(listener)
+        }
+    };
+    Ok(BoundSocket {
+        name: config.name.clone(),
+        fd,
+    })
+}
+
+fn open_sockets(configs: &[SocketConfig]) -> Result<Vec<BoundSocket>, Error> {
+    configs.iter().map(open_socket).collect()
+}
+
+/// Resolves a service's `sockets` names against the pool of currently bound sockets,
+/// keeping only the ones that exist, in the order they'll be dup'd to `3..3+N` — so
+/// `LISTEN_FDNAMES` can be built from the exact same filtered list as the fds themselves
+/// instead of drifting out of sync with an unresolved name in the middle.
+fn resolve_sockets<'a>(
+    service: &ServiceConfig,
+    sockets: &'a [BoundSocket],
+) -> Vec<&'a BoundSocket> {
+    service
+        .sockets
+        .iter()
+        .filter_map(|name| {
+            let socket = sockets.iter().find(|socket| &socket.name == name);
+            if socket.is_none() {
+                warn!(
+                    service = service.title,
+                    socket = name,
+                    "socket refers to an unknown socket, ignoring"
+                );
+            }
+            socket
+        })
+        .collect()
+}
+
+/// Configures `command` so that, on exec, the named `sockets` are available at FDs `3..3+N`
+/// with `LISTEN_FDS`/`LISTEN_PID`/`LISTEN_FDNAMES` set per the systemd socket-activation
+/// convention, letting the service inherit listeners whaleinit keeps bound across restarts.
+fn apply_sockets(
+    command: &mut std::process::Command,
+    service: &ServiceConfig,
+    sockets: &[BoundSocket],
+) {
+    if service.sockets.is_empty() {
+        return;
+    }
+
+    let resolved = resolve_sockets(service, sockets);
+    let fds: Vec<std::os::fd::RawFd> =
+        resolved.iter().map(|socket| socket.fd.as_raw_fd()).collect();
+    let names = resolved
+        .iter()
+        .map(|socket| socket.name.as_str())
+        .collect::<Vec<_>>()
+        .join(":");
+
+    unsafe {
+        command.pre_exec(move || {
+            std::env::set_var("LISTEN_PID", std::process::id().to_string());
+            std::env::set_var("LISTEN_FDS", fds.len().to_string());
+            std::env::set_var("LISTEN_FDNAMES", &names);
+
+            // Stage every source fd above the 3..3+N target window first: if a source fd
+            // already sits inside that window (common, since the first sockets opened tend
+            // to land on 3, 4, 5...), dup2'ing it directly risks one dup2 clobbering a
+            // later source fd before it's consumed. Staging also sidesteps dup2(fd, fd)
+            // being a documented no-op that leaves FD_CLOEXEC set, which would otherwise
+            // have the kernel silently close the socket on exec.
+            let floor = 3 + fds.len() as i32;
+            let mut staged = Vec::with_capacity(fds.len());
+            for fd in &fds {
+                let temp = nix::fcntl::fcntl(*fd, nix::fcntl::FcntlArg::F_DUPFD_CLOEXEC(floor))
+                    .map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
+                staged.push(temp);
+            }
+            for (i, fd) in staged.iter().enumerate() {
+                let target = 3 + i as i32;
+                nix::unistd::dup2(*fd, target)
+                    .map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
+                let clear_cloexec = nix::fcntl::FcntlArg::F_SETFD(nix::fcntl::FdFlag::empty());
+                nix::fcntl::fcntl(target, clear_cloexec)
+                    .map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
+            }
+            Ok(())
+        });
+    }
+}
+
+fn spawn_child(
+    service: &ServiceConfig,
+    sockets: &[BoundSocket],
+) -> Result<std::process::Child, Error> {
     let mut command = std::process::Command::new(&service.exec);
     command.args(&service.args);
+    apply_sockets(&mut command, service, sockets);
 
-    let mut child = command
+    command
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
         .map_err(|e| Error::LaunchService {
             service: service.title.clone(),
             error: e,
-        })?;
+        })
+}
+
+fn should_restart(policy: RestartPolicy, status: Option<&std::process::ExitStatus>) -> bool {
+    match policy {
+        RestartPolicy::Never => false,
+        RestartPolicy::Always => true,
+        RestartPolicy::OnFailure => !status.is_some_and(std::process::ExitStatus::success),
+    }
+}
 
-    info!(pid = child.id(), service = service.title, "service started");
+/// Runs `f` on a helper thread and reports failure if it doesn't finish within `timeout`.
+fn run_with_timeout<F: FnOnce() -> bool + Send + 'static>(f: F, timeout: Duration) -> bool {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.recv_timeout(timeout).unwrap_or(false)
+}
 
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
+fn probe_http(url: &str, status_min: u16, status_max: u16, timeout: Duration) -> bool {
+    let Some(addr) = url.strip_prefix("http://") else {
+        warn!(url, "health probe: only http:// URLs are supported");
+        return false;
+    };
+    let (host, path) = addr.split_once('/').unwrap_or((addr, ""));
+    let Ok(stream) = TcpStream::connect(host) else {
+        return false;
+    };
+    let mut stream = stream;
+    if stream.set_read_timeout(Some(timeout)).is_err()
+        || stream.set_write_timeout(Some(timeout)).is_err()
+    {
+        return false;
+    }
+    if stream
+        .write_all(format!("GET /{path} HTTP/1.0\r\nHost: {host}\r\n\r\n").as_bytes())
+        .is_err()
+    {
+        return false;
+    }
+    let mut line = String::new();
+    if std::io::BufReader::new(stream).read_line(&mut line).is_err() {
+        return false;
+    }
+    line.split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .is_some_and(|code| (status_min..=status_max).contains(&code))
+}
 
-    std::thread::scope(|scope| {
-        scope.spawn(move || {
-            print_log(stdout, &service.title, "stdout");
-        });
+fn probe_once(probe: &HealthProbe) -> bool {
+    let timeout = Duration::from_secs(probe.timeout);
+    match &probe.check {
+        HealthCheck::Exec { exec, args } => {
+            let exec = exec.clone();
+            let args = args.clone();
+            run_with_timeout(
+                move || {
+                    std::process::Command::new(&exec)
+                        .args(&args)
+                        .status()
+                        .is_ok_and(|status| status.success())
+                },
+                timeout,
+            )
+        }
+        HealthCheck::Tcp { addr } => {
+            let Ok(addr) = addr.parse() else {
+                warn!(addr, "health probe: invalid socket address");
+                return false;
+            };
+            TcpStream::connect_timeout(&addr, timeout).is_ok()
+        }
+        HealthCheck::Http {
+            url,
+            status_min,
+            status_max,
+        } => {
+            let (url, status_min, status_max) = (url.clone(), *status_min, *status_max);
+            run_with_timeout(move || probe_http(&url, status_min, status_max, timeout), timeout)
+        }
+    }
+}
 
-        scope.spawn(move || {
-            print_log(stderr, &service.title, "stderr");
-        });
+fn run_health_probe(
+    title: &str,
+    probe: &HealthProbe,
+    service_handle: &ServiceHandle,
+    essential: bool,
+) {
+    let mut consecutive_failures = 0u32;
+    loop {
+        std::thread::sleep(Duration::from_secs(probe.interval));
 
-        match child.wait() {
-            Ok(code) => {
-                info!(
-                    code = code.code(),
-                    service = service.title,
-                    "service exited"
-                );
+        if probe_once(probe) {
+            consecutive_failures = 0;
+            if service_handle.set_health(HealthStatus::Up) != HealthStatus::Up {
+                info!(service = title, "health probe: service is up");
             }
-            Err(e) if e.raw_os_error() == Some(nix::libc::ECHILD) => {
-                trace!("no child process");
+            continue;
+        }
+
+        consecutive_failures += 1;
+        if consecutive_failures < probe.retries {
+            continue;
+        }
+
+        if service_handle.set_health(HealthStatus::Down) != HealthStatus::Down {
+            warn!(service = title, "health probe: service is down");
+            if essential {
+                error!(service = title, "essential service failed health probe");
+                trigger_shutdown();
             }
-            Err(e) => {
-                warn!(
-                    e = e.to_string(),
-                    service = service.title,
-                    "failed to wait for child process"
-                );
+        }
+    }
+}
+
+fn handle(
+    service: &ServiceConfig,
+    service_handle: &ServiceHandle,
+    sockets: &[BoundSocket],
+) -> Result<(), Error> {
+    let mut backoff = Duration::from_secs(service.backoff_initial);
+    let mut retries = 0u32;
+
+    loop {
+        if service_handle.stop_requested() {
+            info!(service = service.title, "service removed from config, not restarting");
+            return Ok(());
+        }
+
+        // This thread never calls a `wait()` variant on its own child, so `reap_children`'s blind
+        // `wait(-1)` can never race a wait here for `pid` and reap it out from
+        // under us (which used to turn a clean exit into an indistinguishable-from-crash
+        // `None` status): we register for notification instead and let `child` drop unwaited.
+        // The registration holds the waiters lock across the spawn itself, so a child that
+        // exits (and gets reaped) before we've inserted blocks `notify_exit_waiter` on the
+        // same lock instead of finding the map empty and dropping the status on the floor.
+        let mut waiters = exit_waiters().lock().unwrap();
+        let mut child = spawn_child(service, sockets)?;
+        service_handle.mark_spawned();
+        let pid = Pid::from_raw(child.id() as i32);
+        service_handle.set_pid(Some(pid));
+        let (tx, rx) = std::sync::mpsc::channel();
+        waiters.insert(pid.as_raw(), tx);
+        drop(waiters);
+
+        info!(pid = child.id(), service = service.title, "service started");
+
+        let stdout = child.stdout.take().unwrap();
+        let stderr = child.stderr.take().unwrap();
+        let started_at = std::time::Instant::now();
+
+        let status = std::thread::scope(|scope| {
+            scope.spawn(move || {
+                print_log(stdout, &service.title, "stdout");
+            });
+
+            scope.spawn(move || {
+                print_log(stderr, &service.title, "stderr");
+            });
+
+            match rx.recv() {
+                Ok(status) => {
+                    info!(
+                        code = status.as_ref().and_then(std::process::ExitStatus::code),
+                        service = service.title,
+                        "service exited"
+                    );
+                    status
+                }
+                Err(_) => {
+                    warn!(service = service.title, "lost exit status for child process");
+                    None
+                }
             }
+        });
+
+        exit_waiters().lock().unwrap().remove(&pid.as_raw());
+        service_handle.set_pid(None);
+
+        if started_at.elapsed() >= HEALTHY_AFTER {
+            backoff = Duration::from_secs(service.backoff_initial);
+            retries = 0;
         }
-        if service.essential {
-            info!("essential service exited");
-            trigger_shutdown(Signal::SIGTERM);
+
+        if service_handle.stop_requested() {
+            info!(service = service.title, "service removed from config, not restarting");
+            return Ok(());
         }
-    });
 
-    Ok(())
-}
+        if !should_restart(service.restart, status.as_ref()) {
+            if service.essential {
+                info!(service = service.title, "essential service exited");
+                trigger_shutdown();
+            }
+            return Ok(());
+        }
 
-extern "C" fn handle_propagational_signal(signal: i32) {
-    let Ok(signal) = nix::sys::signal::Signal::try_from(signal) else {
-        warn!(signal, "invalid signal");
-        return;
-    };
-    if let Err(e) = nix::sys::signal::kill(Pid::from_raw(-1), signal) {
-        error!(
-            error = e.to_string(),
-            signal = signal.as_str(),
-            "failed to send signal"
+        if service.max_retries.is_some_and(|max| retries >= max) {
+            error!(
+                service = service.title,
+                retries, "service exhausted max_retries, giving up"
+            );
+            if service.essential {
+                trigger_shutdown();
+            }
+            return Ok(());
+        }
+
+        retries += 1;
+        warn!(
+            service = service.title,
+            retries,
+            backoff_secs = backoff.as_secs(),
+            "restarting service after backoff"
         );
-    } else {
-        info!(signal = signal.as_str(), "signal sent");
+        std::thread::sleep(backoff);
+        backoff = (backoff * 2).min(Duration::from_secs(service.backoff_max));
+    }
+}
+
+/// Set (async-signal-safely) by `handle_propagational_signal` and polled by
+/// `watch_for_shutdown_signal`, which does the actual (non-async-signal-safe) logging and
+/// escalation on an already-running thread instead of from signal-handler context.
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Only sets a flag: `pthread_create` (what spawning a thread does) and the allocation and
+/// locking `tracing`'s macros perform are not async-signal-safe, so this handler must not do
+/// either directly or it can deadlock the process if the signal lands while the interrupted
+/// thread holds the allocator or subscriber lock.
+extern "C" fn handle_propagational_signal(_signal: i32) {
+    SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Polls the flag `handle_propagational_signal` sets and, once raised, runs the logging and
+/// staged escalation that isn't safe to do from signal-handler context.
+fn watch_for_shutdown_signal() {
+    loop {
+        if SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
+            info!("received shutdown signal, starting staged shutdown");
+            trigger_shutdown();
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
     }
 }
 
@@ -211,6 +819,46 @@ fn set_sigactions() -> Result<(), Error> {
     Ok(())
 }
 
+type ExitSender = std::sync::mpsc::Sender<Option<std::process::ExitStatus>>;
+
+/// Senders registered by `handle()` for each pid it's currently supervising, so the sole
+/// `wait()`-calling thread (`reap_children`) can hand the exit status back to the right
+/// supervisor instead of the two racing over who reaps a given child.
+static EXIT_WAITERS: OnceLock<Mutex<std::collections::HashMap<i32, ExitSender>>> = OnceLock::new();
+
+fn exit_waiters() -> &'static Mutex<std::collections::HashMap<i32, ExitSender>> {
+    EXIT_WAITERS.get_or_init(|| Mutex::new(std::collections::HashMap::new()))
+}
+
+/// Reconstructs the `ExitStatus` a direct `wait()` on `pid` would have produced, using the
+/// same raw-status encoding `std::process::Child::wait` relies on under the hood.
+fn exit_status_of(status: &nix::sys::wait::WaitStatus) -> Option<std::process::ExitStatus> {
+    use std::os::unix::process::ExitStatusExt;
+    match *status {
+        nix::sys::wait::WaitStatus::Exited(_, code) => {
+            Some(std::process::ExitStatus::from_raw((code & 0xff) << 8))
+        }
+        nix::sys::wait::WaitStatus::Signaled(_, signal, _) => {
+            Some(std::process::ExitStatus::from_raw(signal as i32))
+        }
+        _ => None,
+    }
+}
+
+/// Hands a reaped child's exit status to the `handle()` thread supervising it, if any; a
+/// miss means the child was never registered, e.g. an orphaned (reparented) grandchild or an
+/// `exec` health probe's command.
+fn notify_exit_waiter(pid: Pid, status: Option<std::process::ExitStatus>) {
+    match exit_waiters().lock().unwrap().remove(&pid.as_raw()) {
+        Some(sender) => {
+            let _ = sender.send(status);
+        }
+        None => {
+            trace!(pid = pid.as_raw(), "reaped an untracked (orphaned) child");
+        }
+    }
+}
+
 fn reap_children() -> Result<(), Error> {
     loop {
         let status = match nix::sys::wait::wait() {
@@ -227,6 +875,7 @@ fn reap_children() -> Result<(), Error> {
         match status {
             nix::sys::wait::WaitStatus::Exited(pid, code) => {
                 info!(pid = pid.as_raw(), code, "child process exited");
+                notify_exit_waiter(pid, exit_status_of(&status));
             }
             nix::sys::wait::WaitStatus::Signaled(pid, signal, _) => {
                 info!(
@@ -234,6 +883,7 @@ fn reap_children() -> Result<(), Error> {
                     signal = signal.as_str(),
                     "child process signaled"
                 );
+                notify_exit_waiter(pid, exit_status_of(&status));
             }
             nix::sys::wait::WaitStatus::Stopped(pid, signal) => {
                 info!(
@@ -337,27 +987,478 @@ impl TemplateContext {
     }
 }
 
-pub fn run<I: IntoIterator<Item = ServiceConfig>>(services: I) -> Result<(), Error> {
-    set_sigactions()?;
+/// Orders services so that every `after`/`requires` target starts before its dependant,
+/// returning indices into `services`. Unknown dependency titles are ignored with a warning.
+fn topological_order(services: &[ServiceConfig]) -> Result<Vec<usize>, Error> {
+    let index_by_title: std::collections::HashMap<&str, usize> = services
+        .iter()
+        .enumerate()
+        .map(|(i, service)| (service.title.as_str(), i))
+        .collect();
+
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); services.len()];
+    let mut in_degree = vec![0usize; services.len()];
 
-    let mut wait_handlers = Vec::new();
-    for service in services {
-        wait_handlers.push(std::thread::spawn(move || {
-            if let Err(e) = handle(&service) {
+    for (i, service) in services.iter().enumerate() {
+        for dependency in service.after.iter().chain(service.requires.iter()) {
+            match index_by_title.get(dependency.as_str()) {
+                Some(&dep) => {
+                    dependents[dep].push(i);
+                    in_degree[i] += 1;
+                }
+                None => {
+                    warn!(
+                        service = service.title,
+                        dependency, "dependency refers to an unknown service, ignoring"
+                    );
+                }
+            }
+        }
+    }
+
+    let mut queue: std::collections::VecDeque<usize> = (0..services.len())
+        .filter(|&i| in_degree[i] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(services.len());
+
+    while let Some(i) = queue.pop_front() {
+        order.push(i);
+        for &next in &dependents[i] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    if order.len() != services.len() {
+        let cyclic = (0..services.len())
+            .filter(|&i| in_degree[i] > 0)
+            .map(|i| services[i].title.clone())
+            .collect();
+        return Err(Error::DependencyCycle { services: cyclic });
+    }
+
+    Ok(order)
+}
+
+/// A service known to the running supervisor: the config it was started with (for diffing
+/// against a reloaded config) and the handle used to track and stop it.
+struct RunningService {
+    config: ServiceConfig,
+    service_handle: ServiceHandle,
+}
+
+/// Grace period given to a removed/changed service between SIGTERM and SIGKILL on reload.
+const RELOAD_STOP_GRACE: Duration = Duration::from_secs(10);
+
+/// How often the config file and template sources are polled for changes.
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn read_and_parse_config(
+    config_path: &std::path::Path,
+    template_context: &TemplateContext,
+) -> Result<Config, Error> {
+    let raw = std::fs::read_to_string(config_path)
+        .map_err(|e| Error::ReadConfig(config_path.to_path_buf(), e))?;
+    let rendered = template_context
+        .render(&raw)
+        .map_err(|e| Error::RenderConfig(config_path.to_path_buf(), e))?;
+    toml::from_str(&rendered).map_err(|e| Error::ParseConfig(config_path.to_path_buf(), e))
+}
+
+/// Sends SIGTERM to a removed/changed service's current process (if any) and, if it hasn't
+/// exited by the end of `grace`, escalates to SIGKILL.
+fn stop_service(title: &str, service_handle: &ServiceHandle, grace: Duration) {
+    let Some(pid) = service_handle.request_stop() else {
+        return;
+    };
+
+    if let Err(e) = nix::sys::signal::kill(pid, Signal::SIGTERM) {
+        warn!(service = title, error = e.to_string(), "failed to send SIGTERM on reload");
+        return;
+    }
+
+    let deadline = std::time::Instant::now() + grace;
+    while std::time::Instant::now() < deadline {
+        if nix::sys::signal::kill(pid, None).is_err() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    if nix::sys::signal::kill(pid, None).is_ok() {
+        warn!(service = title, "service did not exit within grace period, sending SIGKILL");
+        let _ = nix::sys::signal::kill(pid, Signal::SIGKILL);
+    }
+}
+
+fn spawn_health_probe_thread(service: &ServiceConfig, service_handle: &ServiceHandle) {
+    let Some(probe) = service.health.clone() else {
+        return;
+    };
+    let service_handle = service_handle.clone();
+    let title = service.title.clone();
+    let essential = service.essential;
+    std::thread::spawn(move || run_health_probe(&title, &probe, &service_handle, essential));
+}
+
+fn spawn_service_thread(
+    service: ServiceConfig,
+    service_handle: ServiceHandle,
+    requires: Vec<ServiceHandle>,
+    after: Vec<ServiceHandle>,
+    sockets: Arc<Vec<BoundSocket>>,
+) {
+    std::thread::spawn(move || {
+        for dependency in &requires {
+            while !dependency.is_ready() {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+        for dependency in &after {
+            while !dependency.is_spawned() {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+        if let Err(e) = handle(&service, &service_handle, &sockets) {
+            error!(
+                error = e.to_string(),
+                service = service.title,
+                "failed to handle service"
+            );
+        }
+    });
+}
+
+/// Starts every service in `services`, in topological order, registering each in `running`.
+fn start_services(
+    services: Vec<ServiceConfig>,
+    sockets: &Arc<Vec<BoundSocket>>,
+    running: &mut std::collections::HashMap<String, RunningService>,
+) -> Result<(), Error> {
+    let order = topological_order(&services)?;
+
+    for service in &services {
+        running.insert(
+            service.title.clone(),
+            RunningService {
+                config: service.clone(),
+                service_handle: ServiceHandle::new(service.health.is_some()),
+            },
+        );
+    }
+
+    let mut services: Vec<Option<ServiceConfig>> = services.into_iter().map(Some).collect();
+    for i in order {
+        let service = services[i]
+            .take()
+            .expect("topological_order yields each index exactly once");
+        let running_service = &running[&service.title];
+        spawn_health_probe_thread(&service, &running_service.service_handle);
+        let requires: Vec<ServiceHandle> = service
+            .requires
+            .iter()
+            .filter_map(|title| running.get(title).map(|r| r.service_handle.clone()))
+            .collect();
+        let after: Vec<ServiceHandle> = service
+            .after
+            .iter()
+            .filter_map(|title| running.get(title).map(|r| r.service_handle.clone()))
+            .collect();
+        spawn_service_thread(
+            service,
+            running_service.service_handle.clone(),
+            requires,
+            after,
+            sockets.clone(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Diffs `new_services` against `running`: stops services that were removed or changed,
+/// then (re)starts anything new or changed. Byte-identical services are left untouched.
+fn reconcile_services(
+    new_services: Vec<ServiceConfig>,
+    sockets: &Arc<Vec<BoundSocket>>,
+    running: &mut std::collections::HashMap<String, RunningService>,
+) {
+    let new_titles: std::collections::HashSet<&str> =
+        new_services.iter().map(|s| s.title.as_str()).collect();
+
+    let removed: Vec<String> = running
+        .keys()
+        .filter(|title| !new_titles.contains(title.as_str()))
+        .cloned()
+        .collect();
+    for title in removed {
+        if let Some(running_service) = running.remove(&title) {
+            info!(service = title, "service removed from config, stopping");
+            stop_service(&title, &running_service.service_handle, RELOAD_STOP_GRACE);
+        }
+    }
+
+    let to_start: Vec<ServiceConfig> = new_services
+        .into_iter()
+        .filter(|service| running.get(&service.title).map_or(true, |r| r.config != *service))
+        .collect();
+
+    for service in &to_start {
+        if let Some(running_service) = running.remove(&service.title) {
+            info!(service = service.title, "service config changed, restarting");
+            stop_service(
+                &service.title,
+                &running_service.service_handle,
+                RELOAD_STOP_GRACE,
+            );
+        }
+    }
+
+    if let Err(e) = start_services(to_start, sockets, running) {
+        error!(error = e.to_string(), "failed to start reloaded services");
+    }
+}
+
+fn config_sources(config_path: &std::path::Path, templates: &[Template]) -> Vec<PathBuf> {
+    let mut sources = vec![config_path.to_path_buf()];
+    sources.extend(templates.iter().map(|template| PathBuf::from(&template.src)));
+    sources
+}
+
+fn source_mtimes(paths: &[PathBuf]) -> std::collections::HashMap<PathBuf, std::time::SystemTime> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+            Some((path.clone(), modified))
+        })
+        .collect()
+}
+
+/// Polls `config_path` and the current template sources for changes and, on a change,
+/// re-renders templates and reconciles the running services against the new config.
+/// Runs for the lifetime of the process; this is what keeps `run` from returning.
+///
+/// `[[sockets]]` is NOT hot-reloadable: sockets are opened once in `run`, before this loop
+/// starts, and `socket_configs` is kept only so a changed `[[sockets]]` section can be
+/// detected and warned about instead of silently ignored forever.
+fn watch_config_for_reload(
+    config_path: PathBuf,
+    template_context: TemplateContext,
+    mut templates: Vec<Template>,
+    mut running: std::collections::HashMap<String, RunningService>,
+    sockets: Arc<Vec<BoundSocket>>,
+    socket_configs: Vec<SocketConfig>,
+) {
+    let mut last_seen = source_mtimes(&config_sources(&config_path, &templates));
+
+    loop {
+        std::thread::sleep(RELOAD_POLL_INTERVAL);
+
+        let current = source_mtimes(&config_sources(&config_path, &templates));
+        if current == last_seen {
+            continue;
+        }
+        last_seen = current;
+
+        info!(config = ?config_path, "config change detected, reloading");
+
+        let config = match read_and_parse_config(&config_path, &template_context) {
+            Ok(config) => config,
+            Err(e) => {
                 error!(
                     error = e.to_string(),
-                    service = service.title,
-                    "failed to handle service"
+                    "failed to reload config, keeping previous state"
                 );
+                continue;
             }
-        }));
+        };
+
+        for template in &config.templates {
+            match template_context.render_template(template) {
+                Ok(()) => info!(src = template.src, dest = template.dest, "template re-rendered"),
+                Err(e) => error!(
+                    error = e.to_string(),
+                    src = template.src,
+                    "failed to re-render template on reload"
+                ),
+            }
+        }
+        templates = config.templates;
+
+        if config.sockets != socket_configs {
+            warn!(
+                config = ?config_path,
+                "sockets are not hot-reloadable, ignoring changed [[sockets]] until restart"
+            );
+        }
+
+        reconcile_services(config.services, &sockets, &mut running);
     }
+}
+
+pub fn run(
+    config_path: PathBuf,
+    template_context: TemplateContext,
+    services: Vec<ServiceConfig>,
+    templates: Vec<Template>,
+    sockets: Vec<SocketConfig>,
+    shutdown_signals: Vec<ConfigSignal>,
+    shutdown_grace: u64,
+) -> Result<(), Error> {
+    configure_shutdown(
+        shutdown_signals.into_iter().map(|s| s.0).collect(),
+        Duration::from_secs(shutdown_grace),
+    );
+    set_sigactions()?;
+
+    let socket_configs = sockets.clone();
+    let sockets = Arc::new(open_sockets(&sockets)?);
+
+    let mut running = std::collections::HashMap::new();
+    start_services(services, &sockets, &mut running)?;
 
     std::thread::spawn(reap_children);
+    std::thread::spawn(watch_for_shutdown_signal);
 
-    for wait_handler in wait_handlers {
-        wait_handler.join().unwrap();
-    }
+    watch_config_for_reload(
+        config_path,
+        template_context,
+        templates,
+        running,
+        sockets,
+        socket_configs,
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    fn test_service(title: &str, after: &[&str], requires: &[&str]) -> ServiceConfig {
+        ServiceConfig {
+            title: title.to_string(),
+            exec: "/bin/true".to_string(),
+            args: Vec::new(),
+            essential: false,
+            restart: RestartPolicy::Never,
+            backoff_initial: default_backoff_initial(),
+            backoff_max: default_backoff_max(),
+            max_retries: None,
+            health: None,
+            after: after.iter().map(|s| s.to_string()).collect(),
+            requires: requires.iter().map(|s| s.to_string()).collect(),
+            sockets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn topological_order_respects_after_and_requires() {
+        let services = vec![
+            test_service("c", &["a"], &["b"]),
+            test_service("a", &[], &[]),
+            test_service("b", &[], &[]),
+        ];
+        let order = topological_order(&services).unwrap();
+        let position =
+            |title: &str| order.iter().position(|&i| services[i].title == title).unwrap();
+        assert!(position("a") < position("c"));
+        assert!(position("b") < position("c"));
+    }
+
+    #[test]
+    fn topological_order_ignores_unknown_dependency_and_preserves_order() {
+        let services = vec![
+            test_service("a", &["does-not-exist"], &[]),
+            test_service("b", &[], &[]),
+        ];
+        let order = topological_order(&services).unwrap();
+        assert_eq!(order, vec![0, 1]);
+    }
+
+    #[test]
+    fn topological_order_detects_cycle() {
+        let services = vec![test_service("a", &[], &["b"]), test_service("b", &[], &["a"])];
+        match topological_order(&services) {
+            Err(Error::DependencyCycle { services: cyclic }) => {
+                assert_eq!(cyclic.len(), 2);
+            }
+            other => panic!("expected DependencyCycle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn restart_policy_never_never_restarts() {
+        let success = std::process::ExitStatus::from_raw(0);
+        let failure = std::process::ExitStatus::from_raw(1 << 8);
+        assert!(!should_restart(RestartPolicy::Never, Some(&success)));
+        assert!(!should_restart(RestartPolicy::Never, Some(&failure)));
+        assert!(!should_restart(RestartPolicy::Never, None));
+    }
+
+    #[test]
+    fn restart_policy_on_failure_restarts_on_failure_or_signal_only() {
+        let success = std::process::ExitStatus::from_raw(0);
+        let failure = std::process::ExitStatus::from_raw(1 << 8);
+        let signaled = std::process::ExitStatus::from_raw(9);
+        assert!(!should_restart(RestartPolicy::OnFailure, Some(&success)));
+        assert!(should_restart(RestartPolicy::OnFailure, Some(&failure)));
+        assert!(should_restart(RestartPolicy::OnFailure, Some(&signaled)));
+    }
+
+    #[test]
+    fn restart_policy_always_always_restarts() {
+        let success = std::process::ExitStatus::from_raw(0);
+        let failure = std::process::ExitStatus::from_raw(1 << 8);
+        assert!(should_restart(RestartPolicy::Always, Some(&success)));
+        assert!(should_restart(RestartPolicy::Always, Some(&failure)));
+        assert!(should_restart(RestartPolicy::Always, None));
+    }
+
+    fn bound_socket(name: &str) -> BoundSocket {
+        let file = std::fs::File::open("/dev/null").unwrap();
+        BoundSocket {
+            name: name.to_string(),
+            fd: std::os::fd::OwnedFd::from(file),
+        }
+    }
+
+    #[test]
+    fn resolve_sockets_filters_unknown_names_and_preserves_order() {
+        let sockets = vec![bound_socket("a"), bound_socket("b")];
+        let mut service = test_service("svc", &[], &[]);
+        service.sockets = vec!["b".to_string(), "missing".to_string(), "a".to_string()];
+
+        let resolved = resolve_sockets(&service, &sockets);
+
+        let names: Vec<&str> = resolved.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn resolve_sockets_empty_when_all_unknown() {
+        let sockets = vec![bound_socket("a")];
+        let mut service = test_service("svc", &[], &[]);
+        service.sockets = vec!["missing".to_string()];
+
+        assert!(resolve_sockets(&service, &sockets).is_empty());
+    }
+
+    #[test]
+    fn parse_signal_accepts_bare_and_sig_prefixed_names() {
+        assert_eq!(parse_signal("TERM"), Some(Signal::SIGTERM));
+        assert_eq!(parse_signal("SIGTERM"), Some(Signal::SIGTERM));
+        assert_eq!(parse_signal("sigterm"), Some(Signal::SIGTERM));
+        assert_eq!(parse_signal("KILL"), Some(Signal::SIGKILL));
+    }
+
+    #[test]
+    fn parse_signal_rejects_unknown_name() {
+        assert_eq!(parse_signal("NOTASIGNAL"), None);
+    }
+}
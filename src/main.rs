@@ -67,11 +67,13 @@ fn main() {
     let Config {
         services,
         templates,
-        prehooks,
+        sockets,
+        shutdown_signals,
+        shutdown_grace,
     } = config;
 
-    for template in templates {
-        if let Err(e) = template_context.render_template(&template) {
+    for template in &templates {
+        if let Err(e) = template_context.render_template(template) {
             error!(
                 error = e.to_string(),
                 src = template.src,
@@ -88,21 +90,15 @@ fn main() {
         }
     }
 
-    for prehook in prehooks {
-        if let Err(e) = prehook.run().inspect_err(|e| {
-            error!(error = e.to_string(), "run prehook");
-        }) {
-            error!(
-                error = e.to_string(),
-                prehook = prehook.display_name(),
-                "run prehook"
-            );
-        } else {
-            info!(prehook = prehook.display_name(), "prehook run");
-        }
-    }
-
-    if let Err(e) = whaleinit::run(services) {
+    if let Err(e) = whaleinit::run(
+        opts.config,
+        template_context,
+        services,
+        templates,
+        sockets,
+        shutdown_signals,
+        shutdown_grace,
+    ) {
         error!(error = e.to_string(), "fatal error");
     }
 }